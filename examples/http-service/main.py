@@ -98,6 +98,7 @@ class LogEntry(BaseModel):
     duration_ms: Optional[float] = None
     output: Optional[str] = None
     error: Optional[str] = None
+    extra: Optional[dict] = None
 
 
 class LogBatchRequest(BaseModel):
@@ -119,18 +120,19 @@ def _store_entry(entry: LogEntry) -> dict:
     """Write a single log entry through nfo and return result."""
     from nfo.models import LogEntry as NfoEntry
 
+    kwargs = {"language": entry.language, "env": entry.env}
+    if entry.extra is not None:
+        kwargs["extra"] = entry.extra
+
     nfo_entry = NfoEntry(
         timestamp=NfoEntry.now(),
         level="INFO" if entry.success is not False else "ERROR",
         function_name=entry.cmd,
         module=entry.language,
         args=tuple(entry.args),
-        kwargs={
-            "language": entry.language,
-            "env": entry.env,
-        },
+        kwargs=kwargs,
         arg_types=[type(a).__name__ for a in entry.args],
-        kwarg_types={"language": "str", "env": "str"},
+        kwarg_types={k: type(v).__name__ for k, v in kwargs.items()},
         return_value=entry.output,
         return_type=type(entry.output).__name__ if entry.output is not None else None,
         exception=entry.error,
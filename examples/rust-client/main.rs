@@ -12,14 +12,22 @@
 //
 // Usage:
 //   cargo run --example rust_client
+//   cargo test --example rust_client
 //
 // Environment:
-//   NFO_URL — nfo-service URL (default: http://localhost:8080)
+//   NFO_URL      — nfo-service URL (default: http://localhost:8080)
+//   NFO_LOG_PATH — path entries are posted to (default: /log)
 
+use reqwest::header::USER_AGENT;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use std::env;
-use std::time::Instant;
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
 
 #[derive(Serialize)]
 struct LogEntry<'a> {
@@ -35,6 +43,9 @@ struct LogEntry<'a> {
     output: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     error: Option<String>,
+    /// Free-form JSON payload, sent under the `extra` key.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    extra: Option<serde_json::Value>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -43,28 +54,228 @@ struct LogResponse {
     cmd: Option<String>,
 }
 
+/// Failure threshold and the callback run when it is reached.
+type FailureWarning = (u32, Box<dyn Fn(u32) + Send + Sync>);
+
+/// How `duration_ms` is written on the wire.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+enum DurationFormat {
+    /// Fractional milliseconds, e.g. `1234.5`.
+    #[default]
+    Float,
+    /// Whole milliseconds, rounded to nearest with ties to even, e.g. `1234`.
+    /// Sub-millisecond precision is lost.
+    IntMillis,
+}
+
+/// nfo release this client was written against, sent in the default
+/// `User-Agent`. Bumped with the repository's `VERSION` file (listed in
+/// goal.yaml); `CARGO_PKG_VERSION` would report the version of whichever
+/// package hosts this file instead.
+const NFO_VERSION: &str = "0.2.22";
+
 /// NfoClient sends log entries to the nfo HTTP service.
 struct NfoClient {
     base_url: String,
     client: Client,
+    user_agent: String,
+    log_path: String,
+    fallback: Option<Mutex<Box<dyn Write + Send>>>,
+    consecutive_failures: AtomicU32,
+    failure_warning: Option<FailureWarning>,
+    capture_output: bool,
+    default_success_output: Option<String>,
+    send_permits: Option<Semaphore>,
+    duration_format: DurationFormat,
+    max_args: Option<usize>,
 }
 
 impl NfoClient {
     fn new(base_url: &str) -> Self {
+        Self::with_client(base_url, Client::new())
+    }
+
+    /// Build a client on top of an existing `reqwest::Client`.
+    ///
+    /// `reqwest::Client` is an `Arc` around its connection pool, so passing
+    /// clones of one `Client` to several `NfoClient`s makes them share the pool.
+    fn with_client(base_url: &str, client: Client) -> Self {
         Self {
             base_url: base_url.to_string(),
-            client: Client::new(),
+            client,
+            user_agent: format!("nfo-rust/{}", NFO_VERSION),
+            log_path: "/log".to_string(),
+            fallback: None,
+            consecutive_failures: AtomicU32::new(0),
+            failure_warning: None,
+            capture_output: true,
+            default_success_output: None,
+            send_permits: None,
+            duration_format: DurationFormat::Float,
+            max_args: None,
         }
     }
 
+    /// Override the path entries are posted to (default: `/log`), e.g. when
+    /// nfo-service is mounted under a prefix like `/api/v1/nfo/log`.
+    fn log_path(mut self, path: &str) -> Self {
+        self.log_path = path.to_string();
+        self
+    }
+
+    /// Join `path` onto the base URL with exactly one `/` between them.
+    fn url(&self, path: &str) -> String {
+        format!(
+            "{}/{}",
+            self.base_url.trim_end_matches('/'),
+            path.trim_start_matches('/')
+        )
+    }
+
+    /// Override the `User-Agent` header (default: `nfo-rust/<version>`).
+    fn user_agent(mut self, user_agent: &str) -> Self {
+        self.user_agent = user_agent.to_string();
+        self
+    }
+
+    /// Record the wrapped call's output on success (default: `true`).
+    /// Errors are always recorded.
+    fn capture_output(mut self, enabled: bool) -> Self {
+        self.capture_output = enabled;
+        self
+    }
+
+    /// Output recorded for successful calls when `capture_output` is off.
+    fn default_success_output(mut self, output: Option<String>) -> Self {
+        self.default_success_output = output;
+        self
+    }
+
+    /// Allow at most `limit` sends in flight at once; further sends wait for
+    /// a free slot. Waiting is not counted in `log_timed`'s round-trip.
+    fn max_concurrent_requests(mut self, limit: usize) -> Self {
+        self.send_permits = Some(Semaphore::new(limit));
+        self
+    }
+
+    /// Choose how `duration_ms` is serialized (default: `Float`).
+    fn duration_format(mut self, format: DurationFormat) -> Self {
+        self.duration_format = format;
+        self
+    }
+
+    /// Send at most `limit` args per entry; the rest are replaced by a single
+    /// `"…+N more"` marker.
+    fn max_args(mut self, limit: usize) -> Self {
+        self.max_args = Some(limit);
+        self
+    }
+
+    /// Write entries that fail to send (connection error or non-2xx status)
+    /// to stderr as single JSON lines. Applies to every send: `log`,
+    /// `log_timed` and `log_call`.
+    fn stderr_fallback(mut self, enabled: bool) -> Self {
+        self.fallback = if enabled {
+            Some(Mutex::new(Box::new(io::stderr())))
+        } else {
+            None
+        };
+        self
+    }
+
+    /// Like `stderr_fallback`, but write failed entries to `writer`.
+    fn fallback_writer(mut self, writer: impl Write + Send + 'static) -> Self {
+        self.fallback = Some(Mutex::new(Box::new(writer)));
+        self
+    }
+
+    /// Call `warn` once `threshold` sends in a row have failed. The count
+    /// resets when a send succeeds, so each new outage warns once.
+    fn warn_after_failures(
+        mut self,
+        threshold: u32,
+        warn: impl Fn(u32) + Send + Sync + 'static,
+    ) -> Self {
+        self.failure_warning = Some((threshold, Box::new(warn)));
+        self
+    }
+
     /// Send a single log entry to nfo-service.
     async fn log(&self, entry: &LogEntry<'_>) -> Result<(), reqwest::Error> {
-        self.client
-            .post(format!("{}/log", self.base_url))
-            .json(entry)
+        self.log_timed(entry).await.map(|_| ())
+    }
+
+    /// Send a single log entry and return the HTTP round-trip time.
+    ///
+    /// This is the time spent on the wire, not the `duration_ms` of the
+    /// logged operation. Non-2xx responses are returned as errors, so only
+    /// accepted entries report a latency.
+    async fn log_timed(&self, entry: &LogEntry<'_>) -> Result<Duration, reqwest::Error> {
+        let _permit = match &self.send_permits {
+            Some(permits) => Some(permits.acquire().await.expect("send permits never close")),
+            None => None,
+        };
+        let body = self.wire_body(entry);
+        let start = Instant::now();
+        let sent = self
+            .client
+            .post(self.url(&self.log_path))
+            .header(USER_AGENT, &self.user_agent)
+            .json(&body)
             .send()
-            .await?;
-        Ok(())
+            .await
+            .and_then(|response| response.error_for_status());
+        match sent {
+            Ok(_) => {
+                self.consecutive_failures.store(0, Ordering::Relaxed);
+                Ok(start.elapsed())
+            }
+            Err(e) => {
+                self.record_failure();
+                self.write_fallback(&body);
+                Err(e)
+            }
+        }
+    }
+
+    /// Count a failed send and warn when the run of failures reaches the
+    /// configured threshold.
+    fn record_failure(&self) {
+        let failures = self.consecutive_failures.fetch_add(1, Ordering::Relaxed) + 1;
+        if let Some((threshold, warn)) = &self.failure_warning {
+            if failures == *threshold {
+                warn(failures);
+            }
+        }
+    }
+
+    /// JSON sent for `entry`, with the client's formatting options applied.
+    fn wire_body(&self, entry: &LogEntry<'_>) -> serde_json::Value {
+        let mut body = serde_json::to_value(entry).expect("LogEntry serializes to JSON");
+        if self.duration_format == DurationFormat::IntMillis {
+            if let Some(ms) = body.get("duration_ms").and_then(|ms| ms.as_f64()) {
+                body["duration_ms"] = json!(ms.round_ties_even() as i64);
+            }
+        }
+        if let Some(limit) = self.max_args {
+            if let Some(args) = body["args"].as_array_mut().filter(|a| a.len() > limit) {
+                let dropped = args.len() - limit;
+                args.truncate(limit);
+                args.push(json!(format!("…+{} more", dropped)));
+            }
+        }
+        body
+    }
+
+    /// Last resort for an entry that could not be sent: one JSON line to the
+    /// fallback writer, if configured.
+    fn write_fallback(&self, body: &serde_json::Value) {
+        let Some(fallback) = &self.fallback else {
+            return;
+        };
+        if let Ok(mut writer) = fallback.lock() {
+            let _ = writeln!(writer, "{}", body);
+        }
     }
 
     /// Wrap a function execution with nfo logging and timing.
@@ -84,7 +295,8 @@ impl NfoClient {
         let nfo_env = env::var("NFO_ENV").unwrap_or_else(|_| "prod".to_string());
 
         let (success, output, error) = match &result {
-            Ok(out) => (true, Some(out.clone()), None),
+            Ok(out) if self.capture_output => (true, Some(out.clone()), None),
+            Ok(_) => (true, self.default_success_output.clone(), None),
             Err(e) => (false, None, Some(e.to_string())),
         };
 
@@ -97,6 +309,7 @@ impl NfoClient {
             duration_ms: Some(duration_ms),
             output,
             error,
+            extra: None,
         };
 
         let _ = self.log(&entry).await;
@@ -104,11 +317,27 @@ impl NfoClient {
     }
 }
 
+/// Path of the `/logs` query endpoint for a service whose ingest endpoint is
+/// at `log_path`: the two are mounted side by side, so `/api/v1/nfo/log`
+/// gives `/api/v1/nfo/logs`. `None` if `log_path` does not end in `log`.
+fn logs_path_for(log_path: &str) -> Option<String> {
+    let prefix = log_path.trim_end_matches('/').strip_suffix("log")?;
+    (prefix.is_empty() || prefix.ends_with('/')).then(|| format!("{}logs", prefix))
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let nfo_url =
         env::var("NFO_URL").unwrap_or_else(|_| "http://localhost:8080".to_string());
-    let client = NfoClient::new(&nfo_url);
+    let log_path = env::var("NFO_LOG_PATH").unwrap_or_else(|_| "/log".to_string());
+    let client = NfoClient::new(&nfo_url)
+        .log_path(&log_path)
+        .max_concurrent_requests(16)
+        .max_args(100)
+        .stderr_fallback(true)
+        .warn_after_failures(3, |failures| {
+            eprintln!("nfo: last {} sends failed; is nfo-service up?", failures)
+        });
 
     println!("nfo Rust Client — sending to {}\n", nfo_url);
 
@@ -122,14 +351,23 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         duration_ms: Some(1234.5),
         output: Some("compiled successfully".to_string()),
         error: None,
+        extra: Some(json!({
+            "target": { "triple": "x86_64-unknown-linux-gnu", "opt_level": 3 },
+            "features": ["json"],
+        })),
     };
-    client.log(&entry).await?;
-    println!("Sent: compile --release --target x86_64");
+    match client.log_timed(&entry).await {
+        Ok(rtt) => println!(
+            "Sent: compile --release --target x86_64 ({:.1} ms round-trip)",
+            rtt.as_secs_f64() * 1000.0
+        ),
+        Err(e) => println!("Failed: compile --release --target x86_64 ({})", e),
+    }
 
     // Wrapped function call with timing
     let result = client
         .log_call("process_data", vec!["input.csv"], || {
-            std::thread::sleep(std::time::Duration::from_millis(50));
+            std::thread::sleep(Duration::from_millis(50));
             Ok("processed 1000 rows".to_string())
         })
         .await?;
@@ -143,6 +381,342 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .await;
     println!("Sent: validate bad_input (error logged)");
 
-    println!("\nDone. Query logs: curl {}/logs", nfo_url);
+    // Second client reusing the first one's connection pool.
+    // Its undelivered entries are printed to stdout.
+    // Its successful calls record a fixed marker instead of their output.
+    // Its durations are sent as whole milliseconds.
+    let audit = NfoClient::with_client(&nfo_url, client.client.clone())
+        .user_agent("nfo-audit/1.0")
+        .log_path(&log_path)
+        .fallback_writer(io::stdout())
+        .capture_output(false)
+        .default_success_output(Some("audited".to_string()))
+        .duration_format(DurationFormat::IntMillis);
+    let _ = audit
+        .log_call("audit", vec!["--shared-pool"], || {
+            Ok("checked 1000 records".to_string())
+        })
+        .await;
+    println!("Sent: audit --shared-pool (shared connection pool)");
+
+    match logs_path_for(&log_path) {
+        Some(logs_path) => println!("\nDone. Query logs: curl {}", client.url(&logs_path)),
+        None => println!("\nDone."),
+    }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::Arc;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream};
+
+    /// Local HTTP/1.1 server started by `mock_server`.
+    struct Mock {
+        url: String,
+        requests: Arc<Mutex<Vec<String>>>,
+        peak: Arc<AtomicUsize>,
+    }
+
+    impl Mock {
+        /// Raw requests received so far (head and body), in arrival order.
+        fn requests(&self) -> Vec<String> {
+            self.requests.lock().unwrap().clone()
+        }
+
+        /// Highest number of requests that were being handled at once.
+        fn peak(&self) -> usize {
+            self.peak.load(Ordering::SeqCst)
+        }
+    }
+
+    /// Start a mock that waits `delay`, then answers every request with
+    /// `status` and closes the connection.
+    async fn mock_server(status: u16, delay: Duration) -> Mock {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let (received, highest) = (requests.clone(), peak.clone());
+        let active = Arc::new(AtomicUsize::new(0));
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let (received, highest, active) =
+                    (received.clone(), highest.clone(), active.clone());
+                tokio::spawn(async move {
+                    let request = read_request(&mut socket).await;
+                    received.lock().unwrap().push(request);
+                    let now = active.fetch_add(1, Ordering::SeqCst) + 1;
+                    highest.fetch_max(now, Ordering::SeqCst);
+                    tokio::time::sleep(delay).await;
+                    // Leave the count before answering, so the client cannot
+                    // start its next request while this one is still counted.
+                    active.fetch_sub(1, Ordering::SeqCst);
+                    let response = format!(
+                        "HTTP/1.1 {} Mock\r\nContent-Length: 2\r\nConnection: close\r\n\r\n{{}}",
+                        status
+                    );
+                    let _ = socket.write_all(response.as_bytes()).await;
+                });
+            }
+        });
+        Mock {
+            url,
+            requests,
+            peak,
+        }
+    }
+
+    /// Read one request: the head plus a `Content-Length` body.
+    async fn read_request(socket: &mut TcpStream) -> String {
+        let mut buf = Vec::new();
+        let mut chunk = [0; 4096];
+        loop {
+            let n = socket.read(&mut chunk).await.unwrap_or(0);
+            if n == 0 {
+                break;
+            }
+            buf.extend_from_slice(&chunk[..n]);
+            if let Some(end) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+                let length = String::from_utf8_lossy(&buf[..end])
+                    .lines()
+                    .find_map(|line| header_value(line, "content-length"))
+                    .and_then(|value| value.parse::<usize>().ok())
+                    .unwrap_or(0);
+                if buf.len() >= end + 4 + length {
+                    break;
+                }
+            }
+        }
+        String::from_utf8_lossy(&buf).into_owned()
+    }
+
+    /// Value of `line` if it is the header `name` (case-insensitive).
+    fn header_value<'a>(line: &'a str, name: &str) -> Option<&'a str> {
+        let (key, value) = line.split_once(':')?;
+        key.eq_ignore_ascii_case(name).then_some(value.trim())
+    }
+
+    /// `Write` into a buffer the test keeps a handle on.
+    #[derive(Clone, Default)]
+    struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+    impl SharedBuf {
+        fn contents(&self) -> String {
+            String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+        }
+    }
+
+    impl Write for SharedBuf {
+        fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(bytes);
+            Ok(bytes.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn entry(cmd: &str) -> LogEntry<'_> {
+        LogEntry {
+            cmd,
+            args: vec![],
+            language: "rust",
+            env: "test",
+            success: Some(true),
+            duration_ms: None,
+            output: None,
+            error: None,
+            extra: None,
+        }
+    }
+
+    #[test]
+    fn extra_is_serialized_intact_under_its_key() {
+        let extra = json!({ "target": { "triple": "x86_64", "opt_level": 3 }, "tags": ["a"] });
+        let mut with_extra = entry("compile");
+        with_extra.extra = Some(extra.clone());
+        let value = serde_json::to_value(&with_extra).unwrap();
+        assert_eq!(value["extra"], extra);
+
+        let value = serde_json::to_value(entry("bare")).unwrap();
+        assert!(value.get("extra").is_none());
+    }
+
+    #[test]
+    fn url_joins_base_and_path_with_one_slash() {
+        let cases = [
+            ("http://nfo", "/log", "http://nfo/log"),
+            ("http://nfo/", "/log", "http://nfo/log"),
+            ("http://nfo/", "log", "http://nfo/log"),
+            ("http://nfo", "api/v1/nfo/log", "http://nfo/api/v1/nfo/log"),
+            ("http://nfo", "/api/v1/nfo/log", "http://nfo/api/v1/nfo/log"),
+        ];
+        for (base, path, expected) in cases {
+            let url = NfoClient::new(base).url(path);
+            assert_eq!(url, expected, "{} + {}", base, path);
+        }
+    }
+
+    #[test]
+    fn logs_path_sits_next_to_log_path() {
+        let cases = [
+            ("/log", Some("/logs")),
+            ("log", Some("logs")),
+            ("/api/v1/nfo/log", Some("/api/v1/nfo/logs")),
+            ("/api/v1/nfo/log/", Some("/api/v1/nfo/logs")),
+            ("api/v1/nfo/log", Some("api/v1/nfo/logs")),
+            ("/catalog", None),
+            ("/ingest", None),
+        ];
+        for (log_path, expected) in cases {
+            assert_eq!(logs_path_for(log_path).as_deref(), expected, "{}", log_path);
+        }
+    }
+
+    #[tokio::test]
+    async fn log_posts_to_the_exact_configured_path() {
+        let mock = mock_server(200, Duration::ZERO).await;
+        let base = format!("{}/", mock.url);
+        NfoClient::new(&base)
+            .log_path("/api/v1/nfo/log")
+            .log(&entry("compile"))
+            .await
+            .unwrap();
+        assert!(mock.requests()[0].starts_with("POST /api/v1/nfo/log HTTP/1.1\r\n"));
+    }
+
+    #[tokio::test]
+    async fn log_timed_includes_server_delay() {
+        let mock = mock_server(200, Duration::from_millis(50)).await;
+        let rtt = NfoClient::new(&mock.url)
+            .log_timed(&entry("compile"))
+            .await
+            .unwrap();
+        assert!(rtt >= Duration::from_millis(50), "rtt was {:?}", rtt);
+        assert_eq!(mock.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn log_timed_fails_on_error_status() {
+        let mock = mock_server(501, Duration::ZERO).await;
+        let result = NfoClient::new(&mock.url).log_timed(&entry("compile")).await;
+        assert_eq!(result.unwrap_err().status().map(|s| s.as_u16()), Some(501));
+    }
+
+    /// Value of header `name` in a raw request.
+    fn header<'a>(request: &'a str, name: &str) -> Option<&'a str> {
+        request.lines().find_map(|line| header_value(line, name))
+    }
+
+    #[tokio::test]
+    async fn user_agent_has_a_default_and_can_be_overridden() {
+        let mock = mock_server(200, Duration::ZERO).await;
+        NfoClient::new(&mock.url).log(&entry("a")).await.unwrap();
+        NfoClient::new(&mock.url)
+            .user_agent("custom/1.0")
+            .log(&entry("b"))
+            .await
+            .unwrap();
+
+        let requests = mock.requests();
+        let default = format!("nfo-rust/{}", NFO_VERSION);
+        assert_eq!(header(&requests[0], "user-agent"), Some(default.as_str()));
+        assert_eq!(header(&requests[1], "user-agent"), Some("custom/1.0"));
+    }
+
+    /// JSON body of a raw request.
+    fn body(request: &str) -> serde_json::Value {
+        let (_, body) = request.split_once("\r\n\r\n").unwrap();
+        serde_json::from_str(body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn capture_output_off_drops_success_output_but_keeps_errors() {
+        let mock = mock_server(200, Duration::ZERO).await;
+        let client = NfoClient::new(&mock.url).capture_output(false);
+        client
+            .log_call("ok", vec![], || Ok("large output".to_string()))
+            .await
+            .unwrap();
+        let _ = client.log_call("bad", vec![], || Err("boom".into())).await;
+
+        let requests = mock.requests();
+        assert!(body(&requests[0]).get("output").is_none());
+        assert_eq!(body(&requests[1])["error"], "boom");
+    }
+
+    #[tokio::test]
+    async fn failed_send_is_written_to_fallback_writer() {
+        let mock = mock_server(502, Duration::ZERO).await;
+        let fallback = SharedBuf::default();
+        let client = NfoClient::new(&mock.url).fallback_writer(fallback.clone());
+        let entry = entry("compile");
+        assert!(client.log(&entry).await.is_err());
+
+        let written = fallback.contents();
+        assert_eq!(written.lines().count(), 1);
+        let line: serde_json::Value = serde_json::from_str(&written).unwrap();
+        assert_eq!(line, serde_json::to_value(&entry).unwrap());
+    }
+
+    #[tokio::test]
+    async fn failure_warning_fires_once_per_outage() {
+        let mock = mock_server(503, Duration::ZERO).await;
+        let warnings = Arc::new(AtomicU32::new(0));
+        let counter = warnings.clone();
+        let client = NfoClient::new(&mock.url).warn_after_failures(3, move |_| {
+            counter.fetch_add(1, Ordering::Relaxed);
+        });
+        for _ in 0..10 {
+            let _ = client.log(&entry("compile")).await;
+        }
+        assert_eq!(warnings.load(Ordering::Relaxed), 1);
+    }
+
+    #[tokio::test]
+    async fn max_concurrent_requests_bounds_in_flight_sends() {
+        let mock = mock_server(200, Duration::from_millis(20)).await;
+        let client = Arc::new(NfoClient::new(&mock.url).max_concurrent_requests(5));
+        let sends: Vec<_> = (0..100)
+            .map(|_| {
+                let client = client.clone();
+                tokio::spawn(async move { client.log(&entry("burst")).await })
+            })
+            .collect();
+        for send in sends {
+            send.await.unwrap().unwrap();
+        }
+        assert_eq!(mock.requests().len(), 100);
+        assert!(mock.peak() <= 5, "peak was {}", mock.peak());
+    }
+
+    #[test]
+    fn duration_format_controls_duration_ms() {
+        let mut entry = entry("compile");
+        entry.duration_ms = Some(1234.5);
+        let float = NfoClient::new("http://unused").wire_body(&entry);
+        let int = NfoClient::new("http://unused")
+            .duration_format(DurationFormat::IntMillis)
+            .wire_body(&entry);
+        assert_eq!(float["duration_ms"].to_string(), "1234.5");
+        assert_eq!(int["duration_ms"].to_string(), "1234");
+    }
+
+    #[test]
+    fn max_args_truncates_with_marker() {
+        let mut entry = entry("link");
+        entry.args = vec!["file.o"; 10_000];
+        let body = NfoClient::new("http://unused")
+            .max_args(100)
+            .wire_body(&entry);
+        let args = body["args"].as_array().unwrap();
+        assert_eq!(args.len(), 101);
+        assert_eq!(args[99], "file.o");
+        assert_eq!(args[100], "…+9900 more");
+    }
+}